@@ -0,0 +1,215 @@
+//! Content-defined chunking with a content-addressable chunk store.
+//!
+//! Large inputs are split into variable-length chunks at boundaries chosen by
+//! a Gear-based rolling hash, so inserting or editing a few bytes only redraws
+//! the boundaries of the surrounding chunks. Each unique chunk is stored once
+//! under its digest, and an archive becomes a small index mapping every file
+//! to the chunks that make it up. Two snapshots of a similar tree therefore
+//! share all unchanged chunks on disk.
+
+use std::{io::Read, path::Path};
+
+use anyhow::anyhow;
+
+use crate::{fs::is_interested_file, Result};
+
+/// Target average chunk size as a power of two: `1 << 22` ≈ 4 MiB.
+const AVG_BITS: u32 = 22;
+/// Lower clamp so a run of boundary bytes cannot produce tiny chunks.
+const MIN_SIZE: usize = 1 << 20;
+/// Upper clamp so an unlucky stream without boundaries cannot grow unbounded.
+const MAX_SIZE: usize = 1 << 24;
+
+/// Deterministic Gear table seeded with splitmix64.
+///
+/// A fixed table keeps chunk boundaries stable across runs and machines, which
+/// is what makes the store dedup across snapshots.
+const fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+    let mut i = 0;
+    while i < 256 {
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        table[i] = z;
+        i += 1;
+    }
+    table
+}
+
+const GEAR: [u64; 256] = gear_table();
+
+/// Split `data` into `(offset, len)` chunks at content-defined boundaries.
+fn split(data: &[u8]) -> Vec<(usize, usize)> {
+    let mask: u64 = (1u64 << AVG_BITS) - 1;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    let mut hash: u64 = 0;
+    for (i, byte) in data.iter().enumerate() {
+        hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+        let len = i - start + 1;
+        if (len >= MIN_SIZE && (hash & mask) == 0) || len >= MAX_SIZE {
+            chunks.push((start, len));
+            start = i + 1;
+            hash = 0;
+        }
+    }
+    if start < data.len() {
+        chunks.push((start, data.len() - start));
+    }
+    chunks
+}
+
+/// Write `chunk` into the store keyed by its blake3 digest, returning the
+/// hex digest. Existing chunks are left untouched so storage stays dedup'd.
+fn store_chunk(store_dir: &Path, chunk: &[u8]) -> Result<String> {
+    let digest = blake3::hash(chunk).to_hex().to_string();
+    let path = store_dir.join(&digest);
+    if !path.exists() {
+        std::fs::write(&path, chunk)?;
+    }
+    Ok(digest)
+}
+
+/// Split every interesting file under `srcpath` into chunks, store the unique
+/// ones under `store_dir`, and return the serialized index.
+///
+/// `includes`/`excludes` are the same glob filters as [`is_interested_file`]
+/// (include-precedence, exclude-wins); pass empty slices to chunk everything.
+///
+/// The index is a line-based manifest: `F <relative-path>` starts a file and
+/// each following `C <digest> <offset> <len>` names one of its chunks in
+/// order. [`unpack_chunked`] reverses the process.
+pub fn pack_chunked<P, Q, I, E>(
+    srcpath: P,
+    store_dir: Q,
+    includes: &[I],
+    excludes: &[E],
+) -> Result<Vec<u8>>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+    I: AsRef<str>,
+    E: AsRef<str>,
+{
+    let srcpath = srcpath.as_ref();
+    let store_dir = store_dir.as_ref();
+    std::fs::create_dir_all(store_dir)?;
+
+    let (prefix, walk_root) = if srcpath.is_dir() {
+        (srcpath.to_path_buf(), srcpath.to_path_buf())
+    } else if srcpath.is_file() {
+        let parent = srcpath.parent().unwrap_or(Path::new("")).to_path_buf();
+        (parent, srcpath.to_path_buf())
+    } else {
+        Err(anyhow!(
+            "{} is neither a file or directory",
+            srcpath.display()
+        ))?
+    };
+
+    let mut index = String::new();
+    for entry in walkdir::WalkDir::new(&walk_root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+    {
+        let path = entry.path();
+        if !is_interested_file(&prefix, path, includes, excludes) {
+            continue;
+        }
+        let relative = path.strip_prefix(&prefix)?;
+        let name = relative
+            .to_str()
+            .ok_or(anyhow!("non-utf8 path {}", relative.display()))?;
+
+        let mut data = Vec::new();
+        std::fs::File::open(path)?.read_to_end(&mut data)?;
+
+        index.push_str(&format!("F {name}\n"));
+        for (offset, len) in split(&data) {
+            let digest = store_chunk(store_dir, &data[offset..offset + len])?;
+            index.push_str(&format!("C {digest} {offset} {len}\n"));
+        }
+    }
+    Ok(index.into_bytes())
+}
+
+/// Reassemble the files described by `index_bytes`, fetching each chunk from
+/// `store_dir` and concatenating them under `dstdir`.
+pub fn unpack_chunked<B: AsRef<[u8]>, P: AsRef<Path>, Q: AsRef<Path>>(
+    index_bytes: B,
+    store_dir: P,
+    dstdir: Q,
+) -> Result<()> {
+    let store_dir = store_dir.as_ref();
+    let dstdir = dstdir.as_ref();
+    std::fs::create_dir_all(dstdir)?;
+
+    let index = std::str::from_utf8(index_bytes.as_ref())?;
+    // Running `(file, next-expected-offset)` so each chunk's recorded offset
+    // and length can be validated against the stream as it is reassembled.
+    let mut current: Option<(std::fs::File, usize)> = None;
+    for line in index.lines() {
+        if let Some(name) = line.strip_prefix("F ") {
+            let relative = crate::zip::sanitize_entry_name(name)?;
+            let dstpath = dstdir.join(relative);
+            if let Some(parent) = dstpath.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            current = Some((std::fs::File::create(&dstpath)?, 0));
+        } else if let Some(rest) = line.strip_prefix("C ") {
+            let mut fields = rest.split_whitespace();
+            let digest = fields
+                .next()
+                .ok_or(anyhow!("malformed chunk line: {line}"))?;
+            // A store key is a bare blake3 hex digest; reject anything with
+            // path separators or non-hex bytes so a crafted index cannot read
+            // files outside `store_dir`.
+            if digest.len() != 64 || !digest.bytes().all(|b| b.is_ascii_hexdigit())
+            {
+                Err(anyhow!("invalid chunk digest: {line}"))?;
+            }
+            let offset: usize = fields
+                .next()
+                .and_then(|f| f.parse().ok())
+                .ok_or(anyhow!("malformed chunk offset: {line}"))?;
+            let len: usize = fields
+                .next()
+                .and_then(|f| f.parse().ok())
+                .ok_or(anyhow!("malformed chunk length: {line}"))?;
+            let (file, cursor) = current
+                .as_mut()
+                .ok_or(anyhow!("chunk line before any file: {line}"))?;
+            if offset != *cursor {
+                Err(anyhow!(
+                    "chunk offset {offset} does not follow previous chunk \
+                     (expected {cursor}): {line}"
+                ))?;
+            }
+            let chunk = std::fs::read(store_dir.join(digest))?;
+            if chunk.len() != len {
+                Err(anyhow!(
+                    "chunk {digest} length {} does not match index {len}",
+                    chunk.len()
+                ))?;
+            }
+            // Re-hash the stored bytes: in a content-addressable store the
+            // digest is the contract, so a corrupted or swapped store file
+            // must be rejected even when its length happens to match.
+            if blake3::hash(&chunk).to_hex().as_str() != digest {
+                Err(anyhow!(
+                    "chunk {digest} is corrupt: stored bytes hash to a \
+                     different digest"
+                ))?;
+            }
+            std::io::Write::write_all(file, &chunk)?;
+            *cursor += len;
+        } else if !line.trim().is_empty() {
+            Err(anyhow!("malformed index line: {line}"))?;
+        }
+    }
+    Ok(())
+}
@@ -104,6 +104,7 @@ pub struct Copier {
     dst: PathBuf,
     includes: Vec<String>,
     excludes: Vec<String>,
+    follow_symlinks: bool,
 }
 
 impl Copier {
@@ -120,13 +121,45 @@ impl Copier {
             let src_ref = src.as_path();
             let dst_ref = self.dst.as_path();
             walkdir::WalkDir::new(src_ref)
+                .follow_links(self.follow_symlinks)
                 .into_iter()
                 .par_bridge()
                 .flatten()
-                .filter(|d| d.file_type().is_file())
+                .filter(|d| {
+                    d.file_type().is_file() || d.file_type().is_symlink()
+                })
                 .try_for_each(|entry| -> Result<()> {
                     let source_file = entry.path();
 
+                    // Preserve symlinks verbatim instead of dereferencing them
+                    // through `std::fs::copy`, but keep them subject to the
+                    // same include/exclude filtering as regular files.
+                    if entry.file_type().is_symlink() && !self.follow_symlinks {
+                        let relative_path =
+                            source_file.strip_prefix(src_ref)?;
+                        if !is_interested_name(
+                            relative_path,
+                            &self.includes,
+                            &self.excludes,
+                        ) {
+                            return Ok(());
+                        }
+                        let dest_file = dst_ref.join(relative_path);
+                        if let Some(parent) = dest_file.parent() {
+                            if !parent.exists() {
+                                std::fs::create_dir_all(parent)?;
+                            }
+                        }
+                        let target = std::fs::read_link(source_file)?;
+                        let _ = std::fs::remove_file(&dest_file);
+                        std::os::unix::fs::symlink(target, &dest_file)
+                            .context(format!(
+                                "failed to recreate symlink {}",
+                                dest_file.display()
+                            ))?;
+                        return Ok(());
+                    }
+
                     if !is_interested_file(
                         src_ref,
                         source_file,
@@ -178,6 +211,7 @@ impl CopierBuilder {
                 dst: dst.as_ref().to_path_buf(),
                 includes: vec![],
                 excludes: vec![],
+                follow_symlinks: false,
             },
         }
     }
@@ -197,11 +231,69 @@ impl CopierBuilder {
         self
     }
 
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.copier.follow_symlinks = follow;
+        self
+    }
+
     pub fn build(&self) -> Copier {
         self.copier.clone()
     }
 }
 
+/// Glob-match a relative path that is not backed by a real file.
+///
+/// This is the pattern-matching core of [`is_interested_file`] without the
+/// `is_file` check, so callers iterating over in-memory names (e.g. archive
+/// entries) get identical include-precedence / exclude-wins semantics.
+pub fn is_interested_name<P: AsRef<Path>, I: AsRef<str>, E: AsRef<str>>(
+    relative_path: P,
+    include_patterns: &[I],
+    exclude_patterns: &[E],
+) -> bool {
+    let relative_path = relative_path.as_ref();
+
+    let options = glob::MatchOptions {
+        case_sensitive: !cfg!(windows),
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+    };
+
+    if !exclude_patterns.is_empty() {
+        for pat in exclude_patterns {
+            let pat = pat.as_ref();
+            if Path::new(pat).is_absolute() {
+                continue;
+            }
+            let Ok(pat) = glob::Pattern::new(pat) else {
+                continue;
+            };
+            if pat.matches_path_with(relative_path, options) {
+                return false;
+            }
+        }
+        return true;
+    }
+
+    if !include_patterns.is_empty() {
+        for pat in include_patterns {
+            let pat = pat.as_ref();
+            if Path::new(pat).is_absolute() {
+                continue;
+            }
+            let Ok(pat) = glob::Pattern::new(pat) else {
+                continue;
+            };
+            if pat.matches_path_with(relative_path, options) {
+                return true;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
 /// is_interested_file checks if file `file_path` under directory `root` is
 /// interested by caller with include patterns `include_patterns` and exclude
 /// patterns `exclude_patterns`.
@@ -219,7 +311,6 @@ impl CopierBuilder {
 ///
 /// When both `include_patterns` and `exclude_patterns` are empty, the
 /// result is true.
-///
 pub fn is_interested_file<
     R: AsRef<Path>,
     F: AsRef<Path>,
@@ -247,43 +338,5 @@ pub fn is_interested_file<
         file_path
     };
 
-    let options = glob::MatchOptions {
-        case_sensitive: !cfg!(windows),
-        require_literal_separator: false,
-        require_literal_leading_dot: false,
-    };
-
-    if !exclude_patterns.is_empty() {
-        for pat in exclude_patterns {
-            let pat = pat.as_ref();
-            if Path::new(pat).is_absolute() {
-                continue;
-            }
-            let Ok(pat) = glob::Pattern::new(pat) else {
-                continue;
-            };
-            if pat.matches_path_with(relative_file_path, options) {
-                return false;
-            }
-        }
-        return true;
-    }
-
-    if !include_patterns.is_empty() {
-        for pat in include_patterns {
-            let pat = pat.as_ref();
-            if Path::new(pat).is_absolute() {
-                continue;
-            }
-            let Ok(pat) = glob::Pattern::new(pat) else {
-                continue;
-            };
-            if pat.matches_path_with(relative_file_path, options) {
-                return true;
-            }
-        }
-        return false;
-    }
-
-    true
+    is_interested_name(relative_file_path, include_patterns, exclude_patterns)
 }
@@ -1,5 +1,6 @@
 pub type Result<T> = anyhow::Result<T, anyhow::Error>;
 
+pub mod chunk;
 pub mod cmd;
 pub mod fs;
 pub mod zip;
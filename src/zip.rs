@@ -8,32 +8,183 @@ use anyhow::anyhow;
 
 use crate::Result;
 
+/// Clamp `level` into the range that `method` actually accepts.
+///
+/// The zip crate rejects out-of-range levels at write time; clamping here lets
+/// callers pass a single "effort" number (higher = smaller archive, more CPU)
+/// without having to know each method's valid span.
+fn clamp_level(method: zip::CompressionMethod, level: i64) -> i64 {
+    match method {
+        zip::CompressionMethod::Zstd => level.clamp(-7, 22),
+        zip::CompressionMethod::Deflated => level.clamp(0, 9),
+        zip::CompressionMethod::Bzip2 => level.clamp(1, 9),
+        _ => level,
+    }
+}
+
+/// `S_IFLNK`: the `st_mode` bits that mark an entry as a symbolic link.
+const S_IFLNK: u32 = 0o120000;
+/// Mask selecting the file-type bits out of a Unix mode.
+const S_IFMT: u32 = 0o170000;
+
+/// Packer bundles the compression knobs used to build an archive.
+///
+/// It mirrors [`crate::fs::Copier`]: construct one through [`PackerBuilder`],
+/// then call [`Packer::pack`]. The free [`pack`] function is a thin wrapper
+/// over a default `Packer`.
+#[derive(Debug, Clone)]
+pub struct Packer {
+    method: zip::CompressionMethod,
+    level: Option<i64>,
+    password: Option<String>,
+    follow_symlinks: bool,
+}
+
+impl Default for Packer {
+    fn default() -> Self {
+        Packer {
+            method: zip::CompressionMethod::Zstd,
+            level: None,
+            password: None,
+            follow_symlinks: false,
+        }
+    }
+}
+
+impl Packer {
+    fn options(&self) -> zip::write::SimpleFileOptions {
+        let mut options = zip::write::SimpleFileOptions::default()
+            .compression_method(self.method)
+            .unix_permissions(0o755);
+        // Stored has no level at all; the zip crate rejects one if set.
+        if let Some(level) = self
+            .level
+            .filter(|_| self.method != zip::CompressionMethod::Stored)
+        {
+            options = options
+                .compression_level(Some(clamp_level(self.method, level)));
+        }
+        if let Some(password) = self.password.as_deref() {
+            options = options
+                .with_aes_encryption(zip::AesMode::Aes256, password);
+        }
+        options
+    }
+
+    /// Stream the archive for `srcpath` directly into `writer`.
+    ///
+    /// Directories are walked and zipped entry by entry; a single file is
+    /// written as a one-entry archive keyed by its file name. No temporary
+    /// file is involved, so the sink can be a [`File`], a socket, or a
+    /// seekable wrapper over stdout.
+    pub fn pack_to<P: AsRef<Path>, W: Write + Seek>(
+        &self,
+        srcpath: P,
+        writer: W,
+    ) -> Result<()> {
+        let srcpath = srcpath.as_ref();
+        if srcpath.is_dir() {
+            let walkdir =
+                walkdir::WalkDir::new(srcpath).follow_links(self.follow_symlinks);
+            let it = walkdir.into_iter();
+            zip_dir(
+                &mut it.filter_map(|e| e.ok()),
+                srcpath,
+                writer,
+                self.options(),
+            )?;
+            return Ok(());
+        }
+        if srcpath.is_file() {
+            let name = srcpath
+                .file_name()
+                .and_then(|n| n.to_str())
+                .ok_or(anyhow!("{} has no file name", srcpath.display()))?;
+            let link_meta = std::fs::symlink_metadata(srcpath)?;
+            let mut zip = zip::ZipWriter::new(writer);
+            if link_meta.file_type().is_symlink() && !self.follow_symlinks {
+                // Preserve a top-level symlink as a symlink entry, mirroring
+                // `zip_dir`, instead of writing the target's bytes under an
+                // `S_IFLNK` mode (which unpack would restore as a broken link).
+                let target = std::fs::read_link(srcpath)?;
+                zip.start_file(name, entry_options(self.options(), &link_meta))?;
+                zip.write_all(target.to_string_lossy().as_bytes())?;
+            } else {
+                // `is_file()` already followed the link, so resolve the mode
+                // against the target too and keep body and mode consistent.
+                let meta = std::fs::metadata(srcpath)?;
+                zip.start_file(name, entry_options(self.options(), &meta))?;
+                let mut buffer = Vec::new();
+                File::open(srcpath)?.read_to_end(&mut buffer)?;
+                zip.write_all(&buffer)?;
+            }
+            zip.finish()?;
+            return Ok(());
+        }
+        Err(anyhow!(
+            "{} is neither a file or directory",
+            srcpath.display()
+        ))?
+    }
+
+    pub fn pack<P: AsRef<Path>>(&self, srcpath: P) -> Result<Vec<u8>> {
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        self.pack_to(srcpath, &mut cursor)?;
+        Ok(cursor.into_inner())
+    }
+}
+
+pub fn pack_to<P: AsRef<Path>, W: Write + Seek>(
+    srcpath: P,
+    writer: W,
+) -> Result<()> {
+    Packer::default().pack_to(srcpath, writer)
+}
+
+pub struct PackerBuilder {
+    packer: Packer,
+}
+
+impl PackerBuilder {
+    pub fn new() -> Self {
+        PackerBuilder {
+            packer: Packer::default(),
+        }
+    }
+
+    pub fn method(mut self, method: zip::CompressionMethod) -> Self {
+        self.packer.method = method;
+        self
+    }
+
+    pub fn level(mut self, level: i64) -> Self {
+        self.packer.level = Some(level);
+        self
+    }
+
+    pub fn password<S: AsRef<str>>(mut self, password: S) -> Self {
+        self.packer.password = Some(password.as_ref().to_string());
+        self
+    }
+
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.packer.follow_symlinks = follow;
+        self
+    }
+
+    pub fn build(&self) -> Packer {
+        self.packer.clone()
+    }
+}
+
+impl Default for PackerBuilder {
+    fn default() -> Self {
+        PackerBuilder::new()
+    }
+}
+
 pub fn pack<P: AsRef<Path>>(srcpath: P) -> Result<Vec<u8>> {
-    let file = tempfile::NamedTempFile::new()?;
-    let srcpath = srcpath.as_ref();
-    let dstpath = file.path();
-    let mut content = vec![];
-    if srcpath.is_dir() {
-        let file = File::create(dstpath)?;
-        let walkdir = walkdir::WalkDir::new(srcpath);
-        let it = walkdir.into_iter();
-        zip_dir(
-            &mut it.filter_map(|e| e.ok()),
-            srcpath,
-            file,
-            zip::CompressionMethod::Zstd,
-        )?;
-        let mut f = File::open(dstpath)?;
-        f.read_to_end(&mut content)?;
-        return Ok(content);
-    }
-    if srcpath.is_file() {
-        return Ok(content);
-    }
-    Err(anyhow!(
-        "{} is neither a file or directory",
-        srcpath.display()
-    ))?
+    Packer::default().pack(srcpath)
 }
 
 pub fn unpack<B: AsRef<[u8]>, P: AsRef<Path>>(buf: B, dstdir: P) -> Result<()> {
@@ -50,24 +201,213 @@ pub fn unpack<B: AsRef<[u8]>, P: AsRef<Path>>(buf: B, dstdir: P) -> Result<()> {
         fs::create_dir_all(dstdir)?;
     }
     let mut archiver = zip::ZipArchive::new(buf)?;
-    archiver.extract(dstdir)?;
+    for i in 0..archiver.len() {
+        // Probe the raw entry first: `by_index` itself errors on an AES entry,
+        // so checking encryption on the undecrypted view is what lets the
+        // friendly message fire instead of a generic decrypt failure.
+        if archiver.by_index_raw(i)?.encrypted() {
+            Err(anyhow!(
+                "archive is encrypted, use unpack_encrypted with a password"
+            ))?;
+        }
+        let mut entry = archiver.by_index(i)?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let mode = entry.unix_mode();
+        let is_dir = entry.is_dir();
+        materialize(&mut entry, &name, mode, is_dir, dstdir)?;
+    }
+    Ok(())
+}
+
+/// Restore a single archive entry under `dstdir`, honouring stored Unix mode.
+///
+/// Symlink entries (mode bits `S_IFLNK`) are rebuilt with
+/// [`std::os::unix::fs::symlink`] using their body as the link target; every
+/// other entry gets its permissions reapplied via [`fs::set_permissions`].
+fn materialize<R: Read>(
+    reader: &mut R,
+    name: &Path,
+    mode: Option<u32>,
+    is_dir: bool,
+    dstdir: &Path,
+) -> Result<()> {
+    let dstpath = dstdir.join(name);
+    if is_dir {
+        fs::create_dir_all(&dstpath)?;
+        apply_mode(&dstpath, mode)?;
+        return Ok(());
+    }
+    if let Some(parent) = dstpath.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if matches!(mode, Some(m) if m & S_IFMT == S_IFLNK) {
+        let mut target = String::new();
+        reader.read_to_string(&mut target)?;
+        let _ = fs::remove_file(&dstpath);
+        std::os::unix::fs::symlink(target, &dstpath)?;
+        return Ok(());
+    }
+    let mut out = File::create(&dstpath)?;
+    std::io::copy(reader, &mut out)?;
+    apply_mode(&dstpath, mode)?;
+    Ok(())
+}
+
+fn apply_mode(path: &Path, mode: Option<u32>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    if let Some(mode) = mode {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    }
     Ok(())
 }
 
+/// Normalise an archive entry name into a path guaranteed to stay under the
+/// extraction root.
+///
+/// Components are walked the same way [`crate::fs::abs`] resolves paths:
+/// `.` is dropped, `..` pops the accumulated tail, and any attempt to escape
+/// above the root (a leading `..` or an absolute component) is rejected as a
+/// Zip-Slip attempt.
+pub(crate) fn sanitize_entry_name(name: &str) -> Result<std::path::PathBuf> {
+    use std::path::{Component, PathBuf};
+    let mut ret = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(c) => ret.push(c),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !ret.pop() {
+                    Err(anyhow!("archive entry `{name}` escapes destination"))?;
+                }
+            }
+            Component::RootDir | Component::Prefix(..) => {
+                Err(anyhow!("archive entry `{name}` is not relative"))?;
+            }
+        }
+    }
+    Ok(ret)
+}
+
+/// Extract only the entries of `buf` that match the include/exclude globs.
+///
+/// Each entry name is tested with [`crate::fs::is_interested_name`] so the
+/// include-precedence and exclude-wins rules match the `fs` module exactly,
+/// and [`sanitize_entry_name`] guards every materialised path against
+/// Zip-Slip traversal.
+pub fn unpack_filtered<B, P, I, E>(
+    buf: B,
+    dstdir: P,
+    includes: &[I],
+    excludes: &[E],
+) -> Result<()>
+where
+    B: AsRef<[u8]>,
+    P: AsRef<Path>,
+    I: AsRef<str>,
+    E: AsRef<str>,
+{
+    let buf = std::io::Cursor::new(buf.as_ref());
+    let dstdir = dstdir.as_ref();
+    if dstdir.exists() {
+        if !dstdir.is_dir() {
+            Err(anyhow!(
+                "zip unpack destination {} must be a directory",
+                dstdir.display()
+            ))?;
+        }
+    } else {
+        fs::create_dir_all(dstdir)?;
+    }
+    let mut archiver = zip::ZipArchive::new(buf)?;
+    for i in 0..archiver.len() {
+        let mut entry = archiver.by_index(i)?;
+        let name = entry.name().to_string();
+        let relative = sanitize_entry_name(&name)?;
+        // Gate every entry, directories included, on the glob filter so a
+        // filter like `**/*.toml` materialises only matching files.
+        // `materialize` creates each kept file's parents lazily, so skipping
+        // unmatched directories never drops a needed ancestor.
+        if !crate::fs::is_interested_name(&relative, includes, excludes) {
+            continue;
+        }
+        let mode = entry.unix_mode();
+        let is_dir = entry.is_dir();
+        materialize(&mut entry, &relative, mode, is_dir, dstdir)?;
+    }
+    Ok(())
+}
+
+/// Extract an AES-encrypted archive produced by a password-protected
+/// [`Packer`], decrypting each entry with `password`.
+///
+/// Entries are materialised one by one through
+/// [`zip::ZipArchive::by_index_decrypt`] so that a wrong or missing password
+/// surfaces as a decryption error rather than corrupt output.
+pub fn unpack_encrypted<B: AsRef<[u8]>, P: AsRef<Path>, S: AsRef<str>>(
+    buf: B,
+    dstdir: P,
+    password: S,
+) -> Result<()> {
+    let buf = std::io::Cursor::new(buf.as_ref());
+    let dstdir = dstdir.as_ref();
+    if dstdir.exists() {
+        if !dstdir.is_dir() {
+            Err(anyhow!(
+                "zip unpack destination {} must be a directory",
+                dstdir.display()
+            ))?;
+        }
+    } else {
+        fs::create_dir_all(dstdir)?;
+    }
+    let password = password.as_ref().as_bytes();
+    let mut archiver = zip::ZipArchive::new(buf)?;
+    for i in 0..archiver.len() {
+        let mut entry = archiver.by_index_decrypt(i, password)?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let mode = entry.unix_mode();
+        let is_dir = entry.is_dir();
+        materialize(&mut entry, &name, mode, is_dir, dstdir)?;
+    }
+    Ok(())
+}
+
+/// Apply the mode and mtime from `meta` onto `base`.
+///
+/// The caller picks the metadata: `std::fs::symlink_metadata` for a symlink
+/// stored as a link, so the mode belongs to the link itself, and the target's
+/// metadata for anything written with the target's bytes.
+fn entry_options(
+    base: zip::write::SimpleFileOptions,
+    meta: &std::fs::Metadata,
+) -> zip::write::SimpleFileOptions {
+    use std::os::unix::fs::MetadataExt;
+    let mut options = base.unix_permissions(meta.mode());
+    if let Some(mtime) = meta
+        .modified()
+        .ok()
+        .and_then(|t| zip::DateTime::try_from(time::OffsetDateTime::from(t)).ok())
+    {
+        options = options.last_modified_time(mtime);
+    }
+    options
+}
+
 fn zip_dir<T, P>(
     it: &mut dyn Iterator<Item = walkdir::DirEntry>,
     prefix: P,
     writer: T,
-    method: zip::CompressionMethod,
+    base: zip::write::SimpleFileOptions,
 ) -> Result<()>
 where
     T: Write + Seek,
     P: AsRef<Path>,
 {
     let mut zip = zip::ZipWriter::new(writer);
-    let options = zip::write::SimpleFileOptions::default()
-        .compression_method(method)
-        .unix_permissions(0o755);
     let prefix = prefix.as_ref();
 
     let mut buffer = Vec::new();
@@ -77,18 +417,323 @@ where
             .strip_prefix(prefix)?
             .to_str()
             .ok_or(anyhow!("strip prefix {} failed", prefix.display()))?;
-        if path.is_file() {
-            zip.start_file(name, options)?;
+        // Key off `entry.file_type()` the way `Copier` does: with
+        // `follow_links` on, a symlink-to-file is yielded as a file and
+        // `entry.metadata()` reports the target, so re-`symlink_metadata`ing
+        // the path here would misclassify it and drop the entry.
+        let file_type = entry.file_type();
+        if file_type.is_symlink() {
+            let meta = std::fs::symlink_metadata(path)?;
+            let target = std::fs::read_link(path)?;
+            zip.start_file(name, entry_options(base, &meta))?;
+            zip.write_all(target.to_string_lossy().as_bytes())?;
+        } else if file_type.is_file() {
+            zip.start_file(name, entry_options(base, &entry.metadata()?))?;
             let mut f = File::open(path)?;
 
             f.read_to_end(&mut buffer)?;
             zip.write_all(&buffer)?;
             buffer.clear();
-        } else if !name.is_empty() {
-            zip.add_directory(name, options)?;
+        } else if file_type.is_dir() && !name.is_empty() {
+            zip.add_directory(name, entry_options(base, &entry.metadata()?))?;
         }
     }
     zip.finish()?;
     Ok(())
 }
 
+/// Time-to-live handed back to the kernel for every mounted attribute lookup.
+///
+/// Archive contents never change underneath the mount; the kernel may reuse an
+/// attribute reply for one second before asking again.
+const MOUNT_TTL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// One node of the in-memory tree built from an archive's central directory.
+struct Node {
+    kind: fuser::FileType,
+    size: u64,
+    perm: u16,
+    mtime: std::time::SystemTime,
+    /// Index into the backing [`zip::ZipArchive`] for every node read from an
+    /// entry; `None` for the root and for directories only implied by a path.
+    zip_index: Option<usize>,
+    /// Child name -> inode, for directories.
+    children: std::collections::BTreeMap<String, u64>,
+}
+
+/// Read-only FUSE view over a zip archive held in memory.
+///
+/// The tree is materialised up front from the central directory; file bodies
+/// are decompressed lazily on first access and then cached, so mounting a huge
+/// archive costs only its directory and sequential reads stay linear.
+struct ArchiveFs {
+    archive: zip::ZipArchive<std::io::Cursor<Vec<u8>>>,
+    nodes: std::collections::HashMap<u64, Node>,
+    /// Decompressed entry bodies keyed by inode, filled on demand.
+    bodies: std::collections::HashMap<u64, std::rc::Rc<Vec<u8>>>,
+}
+
+impl ArchiveFs {
+    fn new(bytes: Vec<u8>) -> Result<Self> {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+
+        let mut nodes: std::collections::HashMap<u64, Node> =
+            std::collections::HashMap::new();
+        nodes.insert(
+            1,
+            Node {
+                kind: fuser::FileType::Directory,
+                size: 0,
+                perm: 0o755,
+                mtime: std::time::UNIX_EPOCH,
+                zip_index: None,
+                children: std::collections::BTreeMap::new(),
+            },
+        );
+        let mut next_ino = 2u64;
+
+        for i in 0..archive.len() {
+            let entry = archive.by_index(i)?;
+            let Some(path) = entry.enclosed_name() else {
+                continue;
+            };
+            let mode = entry.unix_mode();
+            let is_symlink =
+                matches!(mode, Some(m) if m & S_IFMT == S_IFLNK);
+            let (kind, default_perm) = if entry.is_dir() {
+                (fuser::FileType::Directory, 0o755)
+            } else if is_symlink {
+                (fuser::FileType::Symlink, 0o777)
+            } else {
+                (fuser::FileType::RegularFile, 0o644)
+            };
+            let perm = mode.map(|m| (m & 0o7777) as u16).unwrap_or(default_perm);
+            let size = entry.size();
+            let mtime = entry
+                .last_modified()
+                .and_then(|dt| dt.to_time().ok())
+                .map(std::time::SystemTime::from)
+                .unwrap_or(std::time::UNIX_EPOCH);
+
+            let components: Vec<String> = path
+                .components()
+                .filter_map(|c| match c {
+                    std::path::Component::Normal(c) => {
+                        Some(c.to_string_lossy().to_string())
+                    }
+                    _ => None,
+                })
+                .collect();
+            if components.is_empty() {
+                continue;
+            }
+
+            let mut parent = 1u64;
+            for (depth, name) in components.iter().enumerate() {
+                let is_leaf = depth + 1 == components.len();
+                if let Some(&ino) = nodes[&parent].children.get(name) {
+                    if is_leaf {
+                        // A real entry landed on a path previously created as an
+                        // implicit parent directory: upgrade its metadata.
+                        let node = nodes.get_mut(&ino).unwrap();
+                        node.kind = kind;
+                        node.size = size;
+                        node.perm = perm;
+                        node.mtime = mtime;
+                        node.zip_index = Some(i);
+                    }
+                    parent = ino;
+                    continue;
+                }
+
+                let ino = next_ino;
+                next_ino += 1;
+                let node = if is_leaf {
+                    Node {
+                        kind,
+                        size,
+                        perm,
+                        mtime,
+                        zip_index: Some(i),
+                        children: std::collections::BTreeMap::new(),
+                    }
+                } else {
+                    Node {
+                        kind: fuser::FileType::Directory,
+                        size: 0,
+                        perm: 0o755,
+                        mtime: std::time::UNIX_EPOCH,
+                        zip_index: None,
+                        children: std::collections::BTreeMap::new(),
+                    }
+                };
+                nodes.insert(ino, node);
+                nodes
+                    .get_mut(&parent)
+                    .unwrap()
+                    .children
+                    .insert(name.clone(), ino);
+                parent = ino;
+            }
+        }
+
+        Ok(ArchiveFs {
+            archive,
+            nodes,
+            bodies: std::collections::HashMap::new(),
+        })
+    }
+
+    fn attr(&self, ino: u64, node: &Node) -> fuser::FileAttr {
+        fuser::FileAttr {
+            ino,
+            size: node.size,
+            blocks: node.size.div_ceil(512),
+            atime: node.mtime,
+            mtime: node.mtime,
+            ctime: node.mtime,
+            crtime: node.mtime,
+            kind: node.kind,
+            perm: node.perm,
+            nlink: 1,
+            uid: 0,
+            gid: 0,
+            rdev: 0,
+            blksize: 512,
+            flags: 0,
+        }
+    }
+
+    /// Decompress the body of the entry backing `ino`, caching it so repeated
+    /// (e.g. sequential) reads of the same file decompress only once.
+    fn body(&mut self, ino: u64) -> Result<std::rc::Rc<Vec<u8>>> {
+        if let Some(body) = self.bodies.get(&ino) {
+            return Ok(body.clone());
+        }
+        let Some(index) = self.nodes.get(&ino).and_then(|n| n.zip_index) else {
+            Err(anyhow!("inode {ino} has no backing entry"))?
+        };
+        let mut entry = self.archive.by_index(index)?;
+        let mut buf = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut buf)?;
+        let body = std::rc::Rc::new(buf);
+        self.bodies.insert(ino, body.clone());
+        Ok(body)
+    }
+}
+
+impl fuser::Filesystem for ArchiveFs {
+    fn lookup(
+        &mut self,
+        _req: &fuser::Request,
+        parent: u64,
+        name: &std::ffi::OsStr,
+        reply: fuser::ReplyEntry,
+    ) {
+        let name = name.to_string_lossy();
+        let child = self
+            .nodes
+            .get(&parent)
+            .and_then(|p| p.children.get(name.as_ref()).copied());
+        match child.and_then(|ino| self.nodes.get(&ino).map(|n| (ino, n))) {
+            Some((ino, node)) => {
+                reply.entry(&MOUNT_TTL, &self.attr(ino, node), 0)
+            }
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn getattr(
+        &mut self,
+        _req: &fuser::Request,
+        ino: u64,
+        reply: fuser::ReplyAttr,
+    ) {
+        match self.nodes.get(&ino) {
+            Some(node) => reply.attr(&MOUNT_TTL, &self.attr(ino, node)),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn readlink(
+        &mut self,
+        _req: &fuser::Request,
+        ino: u64,
+        reply: fuser::ReplyData,
+    ) {
+        match self.body(ino) {
+            Ok(target) => reply.data(&target[..]),
+            Err(_) => reply.error(libc::EIO),
+        }
+    }
+
+    fn read(
+        &mut self,
+        _req: &fuser::Request,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock: Option<u64>,
+        reply: fuser::ReplyData,
+    ) {
+        let body = match self.body(ino) {
+            Ok(body) => body,
+            Err(_) => return reply.error(libc::EIO),
+        };
+        let start = (offset.max(0) as usize).min(body.len());
+        let end = (start + size as usize).min(body.len());
+        reply.data(&body[start..end]);
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &fuser::Request,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: fuser::ReplyDirectory,
+    ) {
+        let Some(node) = self.nodes.get(&ino) else {
+            return reply.error(libc::ENOENT);
+        };
+        let mut entries: Vec<(u64, fuser::FileType, String)> = vec![
+            (ino, fuser::FileType::Directory, ".".to_string()),
+            (ino, fuser::FileType::Directory, "..".to_string()),
+        ];
+        for (name, &child) in node.children.iter() {
+            if let Some(child_node) = self.nodes.get(&child) {
+                entries.push((child, child_node.kind, name.clone()));
+            }
+        }
+        for (i, (ino, kind, name)) in
+            entries.into_iter().enumerate().skip(offset as usize)
+        {
+            if reply.add(ino, (i + 1) as i64, kind, name) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+}
+
+/// Mount the archive held in `archive` as a read-only filesystem at
+/// `mountpoint`.
+///
+/// The call blocks, serving FUSE requests against an in-memory tree built from
+/// the central directory, until the filesystem is unmounted. Entry bodies are
+/// decompressed on demand, so browsing or `grep`ing a large archive never
+/// writes the full tree to disk.
+pub fn mount<B: AsRef<[u8]>, P: AsRef<Path>>(
+    archive: B,
+    mountpoint: P,
+) -> Result<()> {
+    let fs = ArchiveFs::new(archive.as_ref().to_vec())?;
+    let options = vec![
+        fuser::MountOption::RO,
+        fuser::MountOption::FSName("zsnip".to_string()),
+    ];
+    fuser::mount2(fs, mountpoint, &options)?;
+    Ok(())
+}